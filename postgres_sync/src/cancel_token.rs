@@ -0,0 +1,67 @@
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+
+use bytes::BytesMut;
+use postgres_protocol::message::frontend;
+
+use crate::{Client, Error, NoTls, SocketConfig};
+
+/// The capability to cancel the query running on a connection.
+///
+/// It is cheap to clone and doesn't borrow the client, so it can be used from
+/// another thread or stashed in a global, e.g. for a Ctrl-C handler.
+/// Here a watchdog thread cancels a query that runs for too long:
+///
+/// ```no_run
+/// # use postgres_sync::{Client, Error, NoTls};
+/// # fn f(client: &mut Client) -> Result<(), Error> {
+/// let token = client.cancel_token();
+/// std::thread::spawn(move || {
+///     std::thread::sleep(std::time::Duration::from_secs(10));
+///     let _ = token.cancel_query(NoTls);
+/// });
+/// // After 10 seconds this returns an error (SQLSTATE 57014).
+/// client.batch_execute("SELECT pg_sleep(60)")?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CancelToken {
+    addr: SocketAddr,
+    socket_config: SocketConfig,
+    process_id: i32,
+    secret_key: i32,
+}
+
+impl Client {
+    pub fn cancel_token(&self) -> CancelToken {
+        CancelToken {
+            addr: self.peer_addr,
+            socket_config: SocketConfig::new(&self.config),
+            process_id: self.process_id,
+            secret_key: self.secret_key,
+        }
+    }
+}
+
+impl CancelToken {
+    /// Asks the server to cancel the query in progress, if any, on a new connection.
+    ///
+    /// The server doesn't say whether anything was cancelled: a query that is just
+    /// finishing may still succeed.
+    pub fn cancel_query(&self, _tls: NoTls) -> Result<(), Error> {
+        let mut stream = if self.socket_config.uses_proxy() {
+            crate::connect_stream(&self.socket_config)?
+        } else {
+            // Go straight to the server the client is connected to,
+            // in case the host name resolves to several.
+            crate::connect_addr(&self.socket_config, self.addr)?
+        };
+        let mut buf = BytesMut::new();
+        frontend::cancel_request(self.process_id, self.secret_key, &mut buf);
+        stream.write_all(&buf)?;
+        stream.flush()?;
+        // The server closes the connection once it has handled the request.
+        stream.read_to_end(&mut Vec::new())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+//! Asynchronous notifications.
+
+use std::io::ErrorKind;
+use std::time::{Duration, Instant};
+
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::message::backend;
+
+use crate::{Client, DbError, Error};
+
+/// An asynchronous notification sent with `NOTIFY`.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub(crate) process_id: i32,
+    pub(crate) channel: String,
+    pub(crate) payload: String,
+}
+
+impl Notification {
+    /// The process ID of the notifying backend process.
+    pub fn process_id(&self) -> i32 {
+        self.process_id
+    }
+
+    /// The name of the channel that the notify has been raised on.
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// The "payload" string passed from the notifying process.
+    pub fn payload(&self) -> &str {
+        &self.payload
+    }
+}
+
+/// Notifications from a PostgreSQL backend.
+pub struct Notifications<'a> {
+    client: &'a mut Client,
+}
+
+impl Notifications<'_> {
+    /// Returns the number of already buffered pending notifications.
+    pub fn len(&self) -> usize {
+        self.client.notifications.len()
+    }
+
+    /// Determines if there are any already buffered pending notifications.
+    pub fn is_empty(&self) -> bool {
+        self.client.notifications.is_empty()
+    }
+
+    /// Returns a nonblocking iterator over notifications.
+    ///
+    /// Notifications that arrived while running other queries are buffered.
+    /// Once those run out, the iterator reads whatever the server has already sent,
+    /// but doesn't wait for more.
+    pub fn iter(&mut self) -> Iter<'_> {
+        Iter { client: self.client }
+    }
+
+    /// Returns a blocking iterator over notifications.
+    ///
+    /// If there are no buffered notifications, it blocks until the server sends one.
+    pub fn blocking_iter(&mut self) -> BlockingIter<'_> {
+        BlockingIter { client: self.client }
+    }
+
+    /// Returns an iterator over notifications that blocks for at most `timeout` per item.
+    ///
+    /// The iterator ends when no notification arrives in time.
+    pub fn timeout_iter(&mut self, timeout: Duration) -> TimeoutIter<'_> {
+        TimeoutIter { client: self.client, timeout }
+    }
+}
+
+/// A nonblocking iterator over pending notifications.
+pub struct Iter<'a> {
+    client: &'a mut Client,
+}
+
+impl FallibleIterator for Iter<'_> {
+    type Item = Notification;
+    type Error = Error;
+
+    fn next(&mut self) -> Result<Option<Notification>, Error> {
+        self.client.next_notification(Some(Duration::ZERO))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.client.notifications.len(), None)
+    }
+}
+
+/// A blocking iterator over notifications.
+pub struct BlockingIter<'a> {
+    client: &'a mut Client,
+}
+
+impl FallibleIterator for BlockingIter<'_> {
+    type Item = Notification;
+    type Error = Error;
+
+    fn next(&mut self) -> Result<Option<Notification>, Error> {
+        self.client.next_notification(None)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.client.notifications.len(), None)
+    }
+}
+
+/// A time-limited blocking iterator over notifications.
+pub struct TimeoutIter<'a> {
+    client: &'a mut Client,
+    timeout: Duration,
+}
+
+impl FallibleIterator for TimeoutIter<'_> {
+    type Item = Notification;
+    type Error = Error;
+
+    fn next(&mut self) -> Result<Option<Notification>, Error> {
+        self.client.next_notification(Some(self.timeout))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.client.notifications.len(), None)
+    }
+}
+
+impl Client {
+    /// Returns a structure providing access to asynchronous notifications.
+    ///
+    /// Use the `LISTEN` command to register this connection for notifications.
+    pub fn notifications(&mut self) -> Notifications<'_> {
+        Notifications { client: self }
+    }
+
+    /// Returns the next notification, waiting for at most `timeout` (forever for `None`)
+    /// if none is buffered. A zero timeout only reads what has already arrived.
+    fn next_notification(&mut self, timeout: Option<Duration>) -> Result<Option<Notification>, Error> {
+        let deadline = timeout.map(|t| Instant::now() + t);
+        loop {
+            // Between queries, the server only sends asynchronous messages,
+            // or an error right before closing the connection.
+            match self.parse_message()? {
+                Some(backend::Message::ErrorResponse(body)) => {
+                    return Err(DbError::parse(body.fields()).into());
+                }
+                Some(_) => return Err("unexpected message".into()),
+                None => {}
+            }
+            if let Some(notification) = self.notifications.pop_front() {
+                return Ok(Some(notification));
+            }
+            let result = match deadline.map(|d| d.saturating_duration_since(Instant::now())) {
+                None => self.fill_read_buf(),
+                Some(Duration::ZERO) => {
+                    self.stream.set_nonblocking(true)?;
+                    let result = self.fill_read_buf();
+                    self.stream.set_nonblocking(false)?;
+                    result
+                }
+                Some(remaining) => {
+                    self.stream.set_read_timeout(Some(remaining))?;
+                    let result = self.fill_read_buf();
+                    self.stream.set_read_timeout(None)?;
+                    result
+                }
+            };
+            match result {
+                Ok(()) => {}
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => return Ok(None),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
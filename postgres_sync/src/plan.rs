@@ -0,0 +1,61 @@
+use serde_json::Value;
+
+use crate::{Client, Error, ToSql};
+
+impl Client {
+    /// Fails if the plan of `query` has a sequential scan on any of `tables`.
+    ///
+    /// Meant for tests guarding against full table scans. The planner prefers sequential
+    /// scans on small tables, so run it against realistic data, or after
+    /// `SET enable_seqscan = off` to check that an index is usable at all.
+    pub fn assert_uses_index(
+        &mut self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+        tables: &[&str],
+    ) -> Result<(), Error> {
+        let plan = self.explain(query, params)?;
+        let mut scanned = Vec::new();
+        seq_scans(&plan, &mut scanned);
+        match scanned.iter().find(|t| tables.contains(t)) {
+            Some(table) => Err(format!("sequential scan on `{table}` in plan:\n{plan:#}").into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Collects the relations scanned sequentially anywhere in an `EXPLAIN (FORMAT JSON)` plan.
+fn seq_scans<'a>(plan: &'a Value, out: &mut Vec<&'a str>) {
+    match plan {
+        Value::Array(items) => items.iter().for_each(|item| seq_scans(item, out)),
+        Value::Object(node) => {
+            if node.get("Node Type").and_then(Value::as_str) == Some("Seq Scan") {
+                out.extend(node.get("Relation Name").and_then(Value::as_str));
+            }
+            node.values().for_each(|value| seq_scans(value, out));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seq_scans() {
+        let plan: Value = serde_json::from_str(r#"[{"Plan": {
+            "Node Type": "Hash Join",
+            "Plans": [
+                {"Node Type": "Seq Scan", "Relation Name": "small"},
+                {"Node Type": "Hash", "Plans": [
+                    {"Node Type": "Index Scan", "Relation Name": "big"},
+                    {"Node Type": "Seq Scan", "Relation Name": "other"}
+                ]}
+            ]
+        }}]"#).unwrap();
+        let mut scanned = Vec::new();
+        super::seq_scans(&plan, &mut scanned);
+        assert_eq!(scanned, ["small", "other"]);
+    }
+}
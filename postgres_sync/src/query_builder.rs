@@ -0,0 +1,68 @@
+use crate::{Client, Error, Row, ToSql, Transaction};
+
+/// A query with parameters bound one at a time, created by `Client::query_builder`.
+///
+/// ```no_run
+/// # use postgres_sync::{Client, Error};
+/// # fn f(client: &mut Client, id: i32) -> Result<(), Error> {
+/// let row = client
+///     .query_builder("SELECT name FROM users WHERE id = $1 AND active = $2")
+///     .bind(id)
+///     .bind(true)
+///     .fetch_one()?;
+/// # Ok(()) }
+/// ```
+///
+/// Running it fails with an error if the number of `bind` calls doesn't match
+/// the number of parameters in the query.
+pub struct QueryBuilder<'a> {
+    client: &'a mut Client,
+    query: &'a str,
+    params: Vec<Box<dyn ToSql + Sync + 'a>>,
+}
+
+impl<'a> QueryBuilder<'a> {
+    /// Binds the next parameter, `$1` for the first call, `$2` for the second and so on.
+    pub fn bind<T: ToSql + Sync + 'a>(mut self, value: T) -> Self {
+        self.params.push(Box::new(value));
+        self
+    }
+
+    pub fn fetch_all(self) -> Result<Vec<Row>, Error> {
+        let params: Vec<_> = self.params.iter().map(|p| &**p as _).collect();
+        self.client.query(self.query, &params)
+    }
+
+    /// Returns the only row, failing if there are none or more than one.
+    pub fn fetch_one(self) -> Result<Row, Error> {
+        let params: Vec<_> = self.params.iter().map(|p| &**p as _).collect();
+        self.client.query_one(self.query, &params)
+    }
+
+    /// Returns the row if there is one, failing if there is more than one.
+    pub fn fetch_optional(self) -> Result<Option<Row>, Error> {
+        let mut rows = self.fetch_all()?;
+        if rows.len() > 1 {
+            return Err("more than one row returned".into());
+        }
+        Ok(rows.pop())
+    }
+
+    pub fn execute(self) -> Result<u64, Error> {
+        let params: Vec<_> = self.params.iter().map(|p| &**p as _).collect();
+        self.client.execute(self.query, &params)
+    }
+}
+
+impl Client {
+    /// Starts a query whose parameters are supplied with `QueryBuilder::bind`.
+    pub fn query_builder<'a>(&'a mut self, query: &'a str) -> QueryBuilder<'a> {
+        QueryBuilder { client: self, query, params: Vec::new() }
+    }
+}
+
+impl Transaction<'_> {
+    pub fn query_builder<'a>(&'a mut self, query: &'a str) -> QueryBuilder<'a> {
+        self.client.query_builder(query)
+    }
+}
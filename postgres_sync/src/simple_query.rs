@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::message::{backend, frontend};
+
+use crate::types::{FromSql, Type};
+use crate::{AsName, Client, Error, RowIndex, parse_rows_affected};
+
+/// Message returned by `simple_query`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SimpleQueryMessage {
+    /// A row of data.
+    Row(SimpleQueryRow),
+    /// A statement in the query has completed.
+    ///
+    /// The number of rows modified or selected is returned.
+    CommandComplete(u64),
+    /// Column values of the proceeding row values
+    RowDescription(Arc<[SimpleColumn]>),
+}
+
+/// Information about a column of a simple query.
+#[derive(Debug)]
+pub struct SimpleColumn {
+    name: String,
+}
+
+impl SimpleColumn {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl AsName for SimpleColumn {
+    fn as_name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A row of data returned by a simple query. All values are in text format.
+#[derive(Debug)]
+pub struct SimpleQueryRow {
+    columns: Arc<[SimpleColumn]>,
+    values: Vec<Option<Vec<u8>>>,
+    strict: bool,
+}
+
+impl SimpleQueryRow {
+    pub fn columns(&self) -> &[SimpleColumn] {
+        &self.columns
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn get<I: RowIndex + std::fmt::Display>(&self, idx: I) -> Option<&str> {
+        self.try_get(idx).unwrap()
+    }
+
+    pub fn try_get<I: RowIndex + std::fmt::Display>(&self, idx: I) -> Result<Option<&str>, Error> {
+        let idx = crate::column_index(&idx, &self.columns, self.strict)?;
+        FromSql::from_sql_nullable(&Type::TEXT, self.values[idx].as_deref())
+    }
+}
+
+impl Client {
+    /// Executes a sequence of semicolon-separated statements using the simple query protocol,
+    /// returning the resulting rows.
+    ///
+    /// Each statement that returns rows yields a `RowDescription`, followed by its rows,
+    /// followed by a `CommandComplete`, so the result sets of a multi-statement query
+    /// can be told apart.
+    pub fn simple_query(&mut self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
+        frontend::query(query, &mut self.write_buf)?;
+        self.flush()?;
+
+        let mut messages = Vec::new();
+        let mut columns: Option<Arc<[SimpleColumn]>> = None;
+        loop {
+            match self.read_message()? {
+                backend::Message::ReadyForQuery(_) => return Ok(messages),
+                backend::Message::CommandComplete(body) => {
+                    messages.push(SimpleQueryMessage::CommandComplete(parse_rows_affected(&body)?));
+                }
+                backend::Message::EmptyQueryResponse => {
+                    messages.push(SimpleQueryMessage::CommandComplete(0));
+                }
+                backend::Message::RowDescription(body) => {
+                    let cols: Arc<[SimpleColumn]> = body
+                        .fields()
+                        .map(|f| Ok(SimpleColumn { name: f.name().to_string() }))
+                        .collect::<Vec<_>>()?
+                        .into();
+                    columns = Some(cols.clone());
+                    messages.push(SimpleQueryMessage::RowDescription(cols));
+                }
+                backend::Message::DataRow(body) => {
+                    let columns = columns.clone().ok_or("unexpected message")?;
+                    let values = crate::parse_data_row(body)?;
+                    messages.push(SimpleQueryMessage::Row(SimpleQueryRow { columns, values, strict: self.strict }));
+                }
+                backend::Message::ErrorResponse(body) => return Err(self.db_error(body)),
+                _ => return Err("unexpected message".into()),
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! The client side of a SOCKS5 `CONNECT` (RFC 1928), with optional
+//! user name/password authentication (RFC 1929).
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpStream};
+
+use crate::Error;
+
+const NO_AUTH: u8 = 0x00;
+const USER_PASSWORD: u8 = 0x02;
+const NO_ACCEPTABLE_METHOD: u8 = 0xff;
+
+/// Asks the proxy on the other end of `stream` to connect to `host:port`.
+/// On success, the stream carries the connection to the target from then on.
+pub(crate) fn connect(
+    stream: &mut TcpStream,
+    host: &str,
+    port: u16,
+    auth: Option<(&str, &str)>,
+) -> Result<(), Error> {
+    let method = if auth.is_some() { USER_PASSWORD } else { NO_AUTH };
+    stream.write_all(&[5, 1, method])?;
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply)?;
+    if reply[0] != 5 {
+        return Err("SOCKS5 proxy sent an invalid reply".into());
+    }
+    match reply[1] {
+        NO_AUTH if auth.is_none() => {}
+        USER_PASSWORD if auth.is_some() => {
+            let (user, password) = auth.unwrap();
+            let mut request = vec![1];
+            for field in [user, password] {
+                let len = u8::try_from(field.len()).map_err(|_| "SOCKS5 user name or password too long")?;
+                request.push(len);
+                request.extend_from_slice(field.as_bytes());
+            }
+            stream.write_all(&request)?;
+            stream.read_exact(&mut reply)?;
+            // The user name/password subnegotiation has its own version, 1.
+            if reply[0] != 1 {
+                return Err("SOCKS5 proxy sent an invalid reply".into());
+            }
+            if reply[1] != 0 {
+                return Err("SOCKS5 proxy authentication failed".into());
+            }
+        }
+        NO_ACCEPTABLE_METHOD if auth.is_none() => {
+            return Err("SOCKS5 proxy requires authentication, see Config::socks5_auth".into());
+        }
+        _ => return Err("SOCKS5 proxy rejected the authentication method".into()),
+    }
+
+    let mut request = vec![5, 1, 0];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(1);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            // Leave the name for the proxy to resolve.
+            request.push(3);
+            request.push(u8::try_from(host.len()).map_err(|_| "host name too long for SOCKS5")?);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request)?;
+
+    let mut header = [0; 4];
+    stream.read_exact(&mut header)?;
+    if header[0] != 5 {
+        return Err("SOCKS5 proxy sent an invalid reply".into());
+    }
+    if header[1] != 0 {
+        let reason = match header[1] {
+            1 => "general failure",
+            2 => "connection not allowed by ruleset",
+            3 => "network unreachable",
+            4 => "host unreachable",
+            5 => "connection refused",
+            6 => "TTL expired",
+            7 => "command not supported",
+            8 => "address type not supported",
+            _ => "unknown error",
+        };
+        return Err(format!("SOCKS5 proxy could not connect to {host}:{port}: {reason}").into());
+    }
+    // Skip the address the proxy bound for the connection, and its port.
+    let len = match header[3] {
+        1 => 4,
+        4 => 16,
+        3 => {
+            let mut len = [0];
+            stream.read_exact(&mut len)?;
+            len[0] as usize
+        }
+        _ => return Err("SOCKS5 proxy sent an invalid reply".into()),
+    };
+    stream.read_exact(&mut vec![0; len + 2])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    /// Connects to a fake proxy that runs `script` on its end of the connection.
+    fn fake_proxy(script: impl FnOnce(TcpStream) + Send + 'static) -> (TcpStream, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let proxy = thread::spawn(move || script(listener.accept().unwrap().0));
+        (stream, proxy)
+    }
+
+    fn expect(stream: &mut TcpStream, expected: &[u8]) {
+        let mut buf = vec![0; expected.len()];
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, expected);
+    }
+
+    /// A successful CONNECT reply, with an IPv4 bound address.
+    const CONNECTED: &[u8] = &[5, 0, 0, 1, 127, 0, 0, 1, 0x1f, 0x90];
+
+    #[test]
+    fn no_auth() {
+        let (mut stream, proxy) = fake_proxy(|mut s| {
+            expect(&mut s, &[5, 1, NO_AUTH]);
+            s.write_all(&[5, NO_AUTH]).unwrap();
+            expect(&mut s, b"\x05\x01\x00\x03\x06db.lan\x15\x38");
+            s.write_all(CONNECTED).unwrap();
+            s.write_all(b"hi").unwrap();
+        });
+        connect(&mut stream, "db.lan", 5432, None).unwrap();
+        // What follows the reply belongs to the target connection.
+        expect(&mut stream, b"hi");
+        proxy.join().unwrap();
+    }
+
+    #[test]
+    fn user_password() {
+        let (mut stream, proxy) = fake_proxy(|mut s| {
+            expect(&mut s, &[5, 1, USER_PASSWORD]);
+            s.write_all(&[5, USER_PASSWORD]).unwrap();
+            expect(&mut s, b"\x01\x02pu\x02pp");
+            s.write_all(&[1, 0]).unwrap();
+            expect(&mut s, &[5, 1, 0, 1, 10, 0, 0, 1, 0x15, 0x38]);
+            s.write_all(CONNECTED).unwrap();
+        });
+        connect(&mut stream, "10.0.0.1", 5432, Some(("pu", "pp"))).unwrap();
+        proxy.join().unwrap();
+    }
+
+    #[test]
+    fn rejected_auth() {
+        for (reply, error) in [([1, 1], "authentication failed"), ([5, 0], "invalid reply")] {
+            let (mut stream, proxy) = fake_proxy(move |mut s| {
+                expect(&mut s, &[5, 1, USER_PASSWORD]);
+                s.write_all(&[5, USER_PASSWORD]).unwrap();
+                expect(&mut s, b"\x01\x02pu\x02pp");
+                s.write_all(&reply).unwrap();
+            });
+            let e = connect(&mut stream, "db.lan", 5432, Some(("pu", "pp"))).unwrap_err().to_string();
+            assert!(e.contains(error), "{e}");
+            proxy.join().unwrap();
+        }
+        let (mut stream, proxy) = fake_proxy(|mut s| {
+            expect(&mut s, &[5, 1, NO_AUTH]);
+            s.write_all(&[5, NO_ACCEPTABLE_METHOD]).unwrap();
+        });
+        let e = connect(&mut stream, "db.lan", 5432, None).unwrap_err().to_string();
+        assert!(e.contains("requires authentication"), "{e}");
+        proxy.join().unwrap();
+    }
+
+    #[test]
+    fn connect_failed() {
+        let (mut stream, proxy) = fake_proxy(|mut s| {
+            expect(&mut s, &[5, 1, NO_AUTH]);
+            s.write_all(&[5, NO_AUTH]).unwrap();
+            expect(&mut s, b"\x05\x01\x00\x03\x06db.lan\x15\x38");
+            s.write_all(&[5, 5, 0, 1, 0, 0, 0, 0, 0, 0]).unwrap();
+        });
+        let e = connect(&mut stream, "db.lan", 5432, None).unwrap_err().to_string();
+        assert_eq!(e, "SOCKS5 proxy could not connect to db.lan:5432: connection refused");
+        proxy.join().unwrap();
+    }
+}
@@ -1,45 +1,187 @@
-use crate::{BorrowToSql, Client, Error, Row, RowIter, ToSql};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::types::{FromSqlOwned, Type};
+use crate::{BorrowToSql, Client, Column, Error, Row, RowIter, RowRef, SimpleQueryMessage, ToSql};
+
+/// The transaction state of a connection, as of the last completed query.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TransactionStatus {
+    /// Not in a transaction block.
+    Idle,
+    /// In a transaction block.
+    InTransaction,
+    /// In a failed transaction block, where everything but `ROLLBACK` is rejected.
+    Failed,
+}
 
 pub struct Transaction<'a> {
     pub(crate) client: &'a mut Client,
+    savepoint: Option<Savepoint>,
     finished: bool,
 }
 
+/// An SQL cursor opened with `Transaction::cursor`. It is closed when dropped.
+pub struct Cursor<'a> {
+    client: &'a mut Client,
+    /// Already quoted as an identifier.
+    name: String,
+}
+
+impl Cursor<'_> {
+    /// Fetches up to `n` more rows. An empty result means the cursor is exhausted.
+    pub fn fetch(&mut self, n: u32) -> Result<Vec<Row>, Error> {
+        self.client.query(&format!("FETCH {n} FROM {}", self.name), &[])
+    }
+}
+
+impl Drop for Cursor<'_> {
+    fn drop(&mut self) {
+        // In a failed transaction CLOSE is rejected too, and the rollback closes the cursor.
+        if self.client.transaction_status() != TransactionStatus::Failed {
+            let _ = self.client.batch_execute(&format!("CLOSE {}", self.name));
+        }
+    }
+}
+
+/// A savepoint backing a nested transaction.
+struct Savepoint {
+    /// Already quoted as an identifier.
+    name: String,
+    depth: u32,
+}
+
 impl Client {
     pub fn transaction(&mut self) -> Result<Transaction<'_>, Error> {
         self.batch_execute("BEGIN")?;
         Ok(Transaction {
             client: self,
+            savepoint: None,
             finished: false,
         })
     }
 }
 
 impl<'a> Transaction<'a> {
+    /// Starts a nested transaction with an automatically named savepoint.
+    pub fn transaction(&mut self) -> Result<Transaction<'_>, Error> {
+        let depth = self.savepoint.as_ref().map_or(0, |sp| sp.depth) + 1;
+        self.savepoint_inner(format!("sp_{depth}"), depth)
+    }
+
+    /// Starts a nested transaction with a savepoint of the given name.
+    ///
+    /// The name is quoted as an identifier. Committing the nested transaction
+    /// releases the savepoint, rolling it back rolls back to the savepoint.
+    /// As in Postgres, a name that is reused refers to the most recent savepoint.
+    pub fn savepoint<I: Into<String>>(&mut self, name: I) -> Result<Transaction<'_>, Error> {
+        let depth = self.savepoint.as_ref().map_or(0, |sp| sp.depth) + 1;
+        self.savepoint_inner(name.into(), depth)
+    }
+
+    fn savepoint_inner(&mut self, name: String, depth: u32) -> Result<Transaction<'_>, Error> {
+        let name = crate::quote_identifier(&name);
+        self.client.batch_execute(&format!("SAVEPOINT {name}"))?;
+        Ok(Transaction {
+            client: self.client,
+            savepoint: Some(Savepoint { name, depth }),
+            finished: false,
+        })
+    }
+
+    /// Opens an SQL cursor (`DECLARE ... CURSOR`) for a query, to read its rows in batches.
+    ///
+    /// Unlike protocol-level portals, SQL cursors work through connection poolers
+    /// in transaction mode, such as PgBouncer.
+    pub fn cursor(&mut self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<Cursor<'_>, Error> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let name = format!("cursor_{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        self.client.execute(&format!("DECLARE {name} NO SCROLL CURSOR FOR {query}"), params)?;
+        Ok(Cursor { client: self.client, name })
+    }
+
+    fn commit_query(&self) -> String {
+        match &self.savepoint {
+            Some(sp) => format!("RELEASE {}", sp.name),
+            None => "COMMIT".to_string(),
+        }
+    }
+
+    fn rollback_query(&self) -> String {
+        match &self.savepoint {
+            Some(sp) => format!("ROLLBACK TO {}", sp.name),
+            None => "ROLLBACK".to_string(),
+        }
+    }
+
     pub fn commit(mut self) -> Result<(), Error> {
+        if self.client.fail_commit_on_error && self.client.transaction_status() == TransactionStatus::Failed {
+            self.client.batch_execute(&self.rollback_query())?;
+            self.finished = true;
+            return Err("cannot commit a failed transaction, rolled back instead".into());
+        }
         if !self.finished {
-            self.client.batch_execute("COMMIT")?;
+            self.client.batch_execute(&self.commit_query())?;
             self.finished = true;
         }
         Ok(())
     }
 
+    /// Like `commit`, but returns whether the changes were actually committed.
+    ///
+    /// If a statement in the transaction failed, it is rolled back instead and this
+    /// returns `false`. For a nested transaction that means rolling back to its savepoint,
+    /// after which the enclosing transaction can go on.
+    pub fn try_commit(mut self) -> Result<bool, Error> {
+        let failed = self.client.transaction_status() == TransactionStatus::Failed;
+        let query = if failed { self.rollback_query() } else { self.commit_query() };
+        self.client.batch_execute(&query)?;
+        self.finished = true;
+        Ok(!failed)
+    }
+
     pub fn rollback(mut self) -> Result<(), Error> {
         if !self.finished {
-            self.client.batch_execute("ROLLBACK")?;
+            self.client.batch_execute(&self.rollback_query())?;
             self.finished = true;
         }
         Ok(())
     }
 
+    /// Sets configuration parameters until the end of the transaction, like `SET LOCAL`.
+    ///
+    /// E.g. `tx.with_local_settings(&[("statement_timeout", "5s")])` limits the queries
+    /// that follow without touching the session's setting.
+    /// Names and values are bound as parameters, so they need no quoting.
+    /// An empty `settings` doesn't query the server.
+    pub fn with_local_settings(&mut self, settings: &[(&str, &str)]) -> Result<(), Error> {
+        if settings.is_empty() {
+            return Ok(());
+        }
+        let (names, values): (Vec<&str>, Vec<&str>) = settings.iter().copied().unzip();
+        self.client.execute(
+            "SELECT set_config(name, value, true) FROM unnest($1::text[], $2::text[]) AS s (name, value)",
+            &[&names, &values],
+        )?;
+        Ok(())
+    }
+
     pub fn batch_execute(&mut self, query: &str) -> Result<(), Error> {
         self.client.batch_execute(query)
     }
 
+    pub fn simple_query(&mut self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
+        self.client.simple_query(query)
+    }
+
     pub fn execute(&mut self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<u64, Error> {
         self.client.execute(query, params)
     }
 
+    pub fn execute_returning_tag(&mut self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<String, Error> {
+        self.client.execute_returning_tag(query, params)
+    }
+
     pub fn query(&mut self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<Vec<Row>, Error> {
         self.client.query(query, params)
     }
@@ -48,20 +190,63 @@ impl<'a> Transaction<'a> {
         self.client.query_one(query, params)
     }
 
+    pub fn for_each_row<F>(&mut self, query: &str, params: &[&(dyn ToSql + Sync)], f: F) -> Result<(), Error>
+    where
+        F: FnMut(RowRef<'_>) -> Result<(), Error>,
+    {
+        self.client.for_each_row(query, params, f)
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn query_raw_bytes(
+        &mut self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Column>, Vec<Vec<Option<Vec<u8>>>>), Error> {
+        self.client.query_raw_bytes(query, params)
+    }
+
+    pub fn query_scalar<T: FromSqlOwned>(&mut self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<T, Error> {
+        self.client.query_scalar(query, params)
+    }
+
+    pub fn exists(&mut self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<bool, Error> {
+        self.client.exists(query, params)
+    }
+
+    pub fn call(&mut self, proc_name: &str, params: &[&(dyn ToSql + Sync)]) -> Result<Option<Row>, Error> {
+        self.client.call(proc_name, params)
+    }
+
     pub fn query_raw<P, I>(&mut self, query: &str, params: I) -> Result<RowIter, Error>
     where
         P: BorrowToSql,
         I: IntoIterator<Item = P>,
-        I::IntoIter: ExactSizeIterator,
     {
         self.client.query_raw(query, params)
     }
+
+    pub fn query_typed(
+        &mut self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error> {
+        self.client.query_typed(query, params)
+    }
+
+    pub fn query_typed_raw<P, I>(&mut self, query: &str, params: I) -> Result<RowIter, Error>
+    where
+        P: BorrowToSql,
+        I: IntoIterator<Item = (P, Type)>,
+    {
+        self.client.query_typed_raw(query, params)
+    }
 }
 
 impl Drop for Transaction<'_> {
     fn drop(&mut self) {
         if !self.finished {
-            let _ = self.client.batch_execute("ROLLBACK");
+            let _ = self.client.batch_execute(&self.rollback_query());
             self.finished = true;
         }
     }
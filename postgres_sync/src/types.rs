@@ -0,0 +1,278 @@
+//! Types and traits for converting between Rust and Postgres values.
+//!
+//! Everything from `postgres_types` is re-exported here, along with a few
+//! additions specific to this crate.
+
+use std::error::Error as StdError;
+
+use bytes::BytesMut;
+
+pub use postgres_types::*;
+
+/// An untyped SQL `NULL` parameter.
+///
+/// Unlike `Option::<T>::None`, it doesn't require spelling out a Rust type:
+/// it is accepted for any parameter type, and the server infers the type from context.
+#[derive(Debug, Clone, Copy)]
+pub struct Null;
+
+impl ToSql for Null {
+    fn to_sql(&self, _ty: &Type, _out: &mut BytesMut) -> Result<IsNull, Box<dyn StdError + Sync + Send>> {
+        Ok(IsNull::Yes)
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
+}
+
+/// A parameter sent in text format, for the server to parse as the parameter's type.
+///
+/// This is an escape hatch for types without a usable binary `ToSql` impl:
+/// `TextParam("(1,2)")` works for a `point` parameter, for example.
+#[derive(Debug, Clone, Copy)]
+pub struct TextParam<S>(pub S);
+
+impl<S: AsRef<str> + std::fmt::Debug> ToSql for TextParam<S> {
+    fn to_sql(&self, _ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn StdError + Sync + Send>> {
+        out.extend_from_slice(self.0.as_ref().as_bytes());
+        Ok(IsNull::No)
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+
+    fn encode_format(&self, _ty: &Type) -> Format {
+        Format::Text
+    }
+
+    to_sql_checked!();
+}
+
+/// The text of an `xml` value.
+///
+/// The document is passed through as is, without parsing; the server validates it on input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgXml(pub String);
+
+impl<'a> FromSql<'a> for PgXml {
+    fn from_sql(_ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn StdError + Sync + Send>> {
+        Ok(PgXml(String::from_utf8(raw.to_vec())?))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::XML
+    }
+}
+
+impl ToSql for PgXml {
+    fn to_sql(&self, _ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn StdError + Sync + Send>> {
+        out.extend_from_slice(self.0.as_bytes());
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::XML
+    }
+
+    to_sql_checked!();
+}
+
+/// A `time with time zone` (`timetz`) value.
+#[cfg(feature = "with-chrono-0_4")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgTimeTz {
+    /// `24:00:00`, which `NaiveTime` can't hold otherwise, is the leap second
+    /// after `23:59:59` (displayed as `23:59:60`).
+    pub time: chrono::NaiveTime,
+    /// Offset from UTC in seconds, positive east of Greenwich: `+02` is 7200.
+    pub offset_seconds: i32,
+}
+
+#[cfg(feature = "with-chrono-0_4")]
+impl<'a> FromSql<'a> for PgTimeTz {
+    fn from_sql(_ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn StdError + Sync + Send>> {
+        let raw: [u8; 12] = raw.try_into().map_err(|_| "invalid timetz length")?;
+        let micros = i64::from_be_bytes(raw[..8].try_into().unwrap());
+        // Postgres stores the offset in seconds west of Greenwich.
+        let zone = i32::from_be_bytes(raw[8..].try_into().unwrap());
+        let secs = u32::try_from(micros.div_euclid(1_000_000))?;
+        let nanos = micros.rem_euclid(1_000_000) as u32 * 1000;
+        let time = match (secs, nanos) {
+            (86400, 0) => chrono::NaiveTime::from_num_seconds_from_midnight_opt(86399, 1_000_000_000),
+            (0..86400, _) => chrono::NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos),
+            _ => None,
+        };
+        let time = time.ok_or("timetz value out of range")?;
+        Ok(PgTimeTz { time, offset_seconds: -zone })
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::TIMETZ
+    }
+}
+
+#[cfg(feature = "with-chrono-0_4")]
+impl ToSql for PgTimeTz {
+    fn to_sql(&self, _ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn StdError + Sync + Send>> {
+        use chrono::Timelike;
+        let micros = i64::from(self.time.num_seconds_from_midnight()) * 1_000_000
+            + i64::from(self.time.nanosecond() / 1000);
+        out.extend_from_slice(&micros.to_be_bytes());
+        out.extend_from_slice(&(-self.offset_seconds).to_be_bytes());
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::TIMETZ
+    }
+
+    to_sql_checked!();
+}
+
+/// A number of any numeric type converted to `f64`, see `Row::get_lossy_f64`.
+pub(crate) struct LossyF64(pub f64);
+
+impl<'a> FromSql<'a> for LossyF64 {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn StdError + Sync + Send>> {
+        let value = match *ty {
+            Type::INT2 => f64::from(i16::from_sql(ty, raw)?),
+            Type::INT4 => f64::from(i32::from_sql(ty, raw)?),
+            Type::INT8 => i64::from_sql(ty, raw)? as f64,
+            Type::FLOAT4 => f64::from(f32::from_sql(ty, raw)?),
+            Type::FLOAT8 => f64::from_sql(ty, raw)?,
+            _ => numeric_to_f64(raw)?,
+        };
+        Ok(LossyF64(value))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(*ty, Type::INT2 | Type::INT4 | Type::INT8 | Type::FLOAT4 | Type::FLOAT8 | Type::NUMERIC)
+    }
+}
+
+/// Converts a `numeric` in the binary format: base-10000 digits with a weight and a sign.
+fn numeric_to_f64(raw: &[u8]) -> Result<f64, Box<dyn StdError + Sync + Send>> {
+    let header = |i: usize| raw.get(i..i + 2).map(|b| u16::from_be_bytes([b[0], b[1]])).ok_or("invalid numeric");
+    let ndigits = usize::from(header(0)?);
+    let weight = header(2)? as i16;
+    let sign = header(4)?;
+    match sign {
+        0x0000 | 0x4000 => {}
+        0xC000 => return Ok(f64::NAN),
+        0xD000 => return Ok(f64::INFINITY),
+        0xF000 => return Ok(f64::NEG_INFINITY),
+        _ => return Err("invalid numeric sign".into()),
+    }
+    if raw.len() != 8 + 2 * ndigits {
+        return Err("invalid numeric length".into());
+    }
+    // Going through a decimal string gets the rounding right.
+    let mut s = String::from(if sign == 0x4000 { "-0." } else { "0." });
+    for i in 0..ndigits {
+        s.push_str(&format!("{:04}", header(8 + 2 * i)?));
+    }
+    s.push_str(&format!("e{}", (i32::from(weight) + 1) * 4));
+    Ok(s.parse()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn null() {
+        let mut buf = BytesMut::new();
+        for ty in [Type::INT4, Type::TEXT, Type::JSONB] {
+            assert!(matches!(Null.to_sql_checked(&ty, &mut buf).unwrap(), IsNull::Yes));
+        }
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn text_param() {
+        let mut buf = BytesMut::new();
+        let param = TextParam("(1,2)");
+        assert!(matches!(param.to_sql_checked(&Type::POINT, &mut buf).unwrap(), IsNull::No));
+        assert!(matches!(param.encode_format(&Type::POINT), Format::Text));
+        assert_eq!(&buf[..], b"(1,2)");
+    }
+
+    #[cfg(feature = "with-chrono-0_4")]
+    #[test]
+    fn timetz() {
+        let value = PgTimeTz {
+            time: chrono::NaiveTime::from_hms_micro_opt(12, 34, 56, 789).unwrap(),
+            offset_seconds: 7200,
+        };
+        let mut buf = BytesMut::new();
+        value.to_sql_checked(&Type::TIMETZ, &mut buf).unwrap();
+        assert_eq!(&buf[8..], (-7200i32).to_be_bytes());
+        assert_eq!(PgTimeTz::from_sql(&Type::TIMETZ, &buf).unwrap(), value);
+        assert!(PgTimeTz::from_sql(&Type::TIMETZ, &buf[..8]).is_err());
+
+        let raw = |micros: i64| [micros.to_be_bytes(), [0; 8]].concat()[..12].to_vec();
+        let midnight = PgTimeTz::from_sql(&Type::TIMETZ, &raw(86_400_000_000)).unwrap();
+        assert_eq!(midnight.time.to_string(), "23:59:60");
+        let mut buf = BytesMut::new();
+        midnight.to_sql_checked(&Type::TIMETZ, &mut buf).unwrap();
+        assert_eq!(&buf[..], raw(86_400_000_000));
+        assert!(PgTimeTz::from_sql(&Type::TIMETZ, &raw(86_400_000_001)).is_err());
+        assert!(PgTimeTz::from_sql(&Type::TIMETZ, &raw(-1)).is_err());
+
+        assert_eq!(Option::<PgTimeTz>::from_sql_nullable(&Type::TIMETZ, None).unwrap(), None);
+        assert!(PgTimeTz::from_sql_nullable(&Type::TIMETZ, None).is_err());
+    }
+
+    #[cfg(feature = "with-chrono-0_4")]
+    #[test]
+    #[ignore = "needs a Postgres server at $DATABASE_URL"]
+    fn timetz_server_round_trip() {
+        let url = std::env::var("DATABASE_URL").unwrap();
+        let mut client = crate::Client::connect(&url, crate::NoTls).unwrap();
+        let row = client.query_one("SELECT '12:34:56+02'::timetz, '24:00:00-03:30'::timetz, NULL::timetz", &[]).unwrap();
+        let value: PgTimeTz = row.get(0);
+        assert_eq!(value.time, chrono::NaiveTime::from_hms_opt(12, 34, 56).unwrap());
+        assert_eq!(value.offset_seconds, 7200);
+        assert_eq!(row.get::<_, Option<PgTimeTz>>(2), None);
+        for (i, text) in [(0, "12:34:56+02"), (1, "24:00:00-03:30")] {
+            let value: PgTimeTz = row.get(i);
+            let back: String = client.query_one("SELECT $1::timetz::text", &[&value]).unwrap().get(0);
+            assert_eq!(back, text);
+        }
+    }
+
+    #[test]
+    fn lossy_f64() {
+        let numeric = |weight: i16, sign: u16, digits: &[u16]| {
+            let mut raw = Vec::new();
+            for n in [digits.len() as u16, weight as u16, sign, 0].into_iter().chain(digits.iter().copied()) {
+                raw.extend_from_slice(&n.to_be_bytes());
+            }
+            LossyF64::from_sql(&Type::NUMERIC, &raw).map(|v| v.0)
+        };
+        // 12345.678
+        assert_eq!(numeric(1, 0, &[1, 2345, 6780]).unwrap(), 12345.678);
+        // -0.0001
+        assert_eq!(numeric(-1, 0x4000, &[1]).unwrap(), -0.0001);
+        assert_eq!(numeric(0, 0, &[]).unwrap(), 0.0);
+        assert!(numeric(0, 0xC000, &[]).unwrap().is_nan());
+        assert_eq!(numeric(0, 0xF000, &[]).unwrap(), f64::NEG_INFINITY);
+        assert_eq!(numeric(0, 0, &[1]).unwrap(), 1.0);
+        assert!(LossyF64::from_sql(&Type::NUMERIC, &[0, 2, 0, 0, 0, 0, 0, 0, 0, 1]).is_err());
+        assert_eq!(LossyF64::from_sql(&Type::INT8, &7i64.to_be_bytes()).unwrap().0, 7.0);
+        assert!(!LossyF64::accepts(&Type::TEXT));
+    }
+
+    #[test]
+    fn xml() {
+        let doc = PgXml("<a>ü</a>".to_string());
+        let mut buf = BytesMut::new();
+        doc.to_sql_checked(&Type::XML, &mut buf).unwrap();
+        assert_eq!(PgXml::from_sql(&Type::XML, &buf).unwrap(), doc);
+        assert!(doc.to_sql_checked(&Type::TEXT, &mut buf).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+//! Derive macros for `postgres_sync`. Use them through its `derive` feature.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, Path, parse_macro_input};
+
+/// Implements `FromRow` by reading each field from the column of the same name.
+///
+/// `#[postgres(name = "...")]` on a field reads it from a differently named column.
+/// `#[postgres(crate = "...")]` on the struct sets the path of the `postgres_sync` crate,
+/// which is assumed to be available as `postgres`, as suggested by its README.
+#[proc_macro_derive(FromRow, attributes(postgres))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match from_row(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn from_row(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut krate: Path = syn::parse_quote!(::postgres);
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("postgres")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                krate = meta.value()?.parse::<LitStr>()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(input, "FromRow can only be derived for structs"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(input, "FromRow requires named fields"));
+    };
+    let mut inits = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().unwrap();
+        let mut name = ident.to_string();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("postgres")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported attribute"))
+                }
+            })?;
+        }
+        inits.push(quote! { #ident: row.try_get(#name)? });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::FromRow for #ident #ty_generics #where_clause {
+            fn from_row(row: &#krate::Row) -> ::std::result::Result<Self, #krate::Error> {
+                ::std::result::Result::Ok(Self { #(#inits),* })
+            }
+        }
+    })
+}
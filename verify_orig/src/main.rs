@@ -15,10 +15,43 @@ fn main() {
     assert_eq!(v, 2);
     eprintln!("ok");
 
+    eprint!("Config::ssl_mode ... ");
+    let mut config = s.parse::<postgres::Config>().unwrap();
+    config.ssl_mode(postgres::config::SslMode::Disable);
+    config.connect(postgres::NoTls).unwrap();
+    config.ssl_mode(postgres::config::SslMode::Require);
+    assert!(config.connect(postgres::NoTls).is_err());
+    eprintln!("ok");
+
+    eprint!("Config::channel_binding ... ");
+    let mut config = s.parse::<postgres::Config>().unwrap();
+    config.channel_binding(postgres::config::ChannelBinding::Disable);
+    config.connect(postgres::NoTls).unwrap();
+    config.channel_binding(postgres::config::ChannelBinding::Require);
+    let e = config.connect(postgres::NoTls).err().unwrap().to_string();
+    assert!(e.contains("server did not use channel binding"), "{e}");
+    eprintln!("ok");
+
+    eprint!("Config::options, Config::application_name ... ");
+    let mut config = s.parse::<postgres::Config>().unwrap();
+    config.options("-c statement_timeout=1234");
+    config.application_name("verify");
+    let mut other = config.connect(postgres::NoTls).unwrap();
+    let v: String = other.query_one("SHOW statement_timeout", &[]).unwrap().get(0);
+    assert_eq!(v, "1234ms");
+    let v: String = other.query_one("SHOW application_name", &[]).unwrap().get(0);
+    assert_eq!(v, "verify");
+    eprintln!("ok");
+
     eprint!("Client::connect ... ");
     let mut client = postgres::Client::connect(&s, postgres::NoTls).unwrap();
     eprintln!("ok");
 
+    eprint!("Client::close ... ");
+    let other = postgres::Client::connect(&s, postgres::NoTls).unwrap();
+    other.close().unwrap();
+    eprintln!("ok");
+
     eprint!("SELECT 2 + 2 ... ");
     let row = client
         .query_one("SELECT 2 + 2", &[])
@@ -41,6 +74,65 @@ fn main() {
     assert_eq!(result, 4);
     eprintln!("ok");
 
+    eprint!("reused parameter ... ");
+    let row = client
+        .query_one("SELECT $1::INT4 + $1, $1 = 2 OR $1 = 3", &[&2i32])
+        .unwrap();
+    assert_eq!((row.get::<_, i32>(0), row.get::<_, bool>(1)), (4, true));
+    let e = client.query("SELECT $1::INT4 + $1", &[&2i32, &2i32]).err().unwrap().to_string();
+    assert!(e.contains("expected 1 parameters but got 2"), "{e}");
+    eprintln!("ok");
+
+    eprint!("wrong number of parameters ... ");
+    let e = client.query("SELECT $1::INT4, $2::INT4", &[&1i32]).err().unwrap().to_string();
+    assert!(e.contains("expected 2 parameters but got 1"), "{e}");
+    client.query_one("SELECT 1", &[]).unwrap();
+    eprintln!("ok");
+
+    eprint!("query_typed ... ");
+    let rows = client
+        .query_typed("SELECT $1", &[(&42i32, postgres::types::Type::INT4)])
+        .unwrap();
+    let v: i32 = rows[0].get(0);
+    assert_eq!(v, 42);
+    eprintln!("ok");
+
+    eprint!("bool ... ");
+    let row = client
+        .query_one("SELECT true, false, NULL::BOOL, ARRAY[true, false, NULL]", &[])
+        .unwrap();
+    assert!(row.get::<_, bool>(0));
+    assert!(!row.get::<_, bool>(1));
+    assert_eq!(row.get::<_, Option<bool>>(2), None);
+    assert_eq!(row.get::<_, Vec<Option<bool>>>(3), [Some(true), Some(false), None]);
+    let row = client
+        .query_one("SELECT $1::BOOL, $2::BOOL[]", &[&true, &vec![false, true]])
+        .unwrap();
+    assert!(row.get::<_, bool>(0));
+    assert_eq!(row.get::<_, Vec<bool>>(1), [false, true]);
+    let e = row.try_get::<_, Vec<String>>(1).unwrap_err().to_string();
+    assert!(e.contains("Postgres type `_bool`"), "{e}");
+    let messages = client.simple_query("SELECT true, false").unwrap();
+    let postgres::SimpleQueryMessage::Row(row) = &messages[1] else { panic!() };
+    assert_eq!((row.get(0), row.get(1)), (Some("t"), Some("f")));
+    eprintln!("ok");
+
+    eprint!("\"char\" ... ");
+    let row = client.query_one("SELECT relkind FROM pg_class WHERE relname = 'pg_class'", &[]).unwrap();
+    assert_eq!(row.get::<_, i8>("relkind"), b'r' as i8);
+    let row = client.query_one("SELECT $1::\"char\"::TEXT", &[&(b'v' as i8)]).unwrap();
+    assert_eq!(row.get::<_, &str>(0), "v");
+    eprintln!("ok");
+
+    eprint!("pg_lsn ... ");
+    let row = client.query_one("SELECT '16/B374D848'::PG_LSN", &[]).unwrap();
+    let lsn: postgres::types::PgLsn = row.get(0);
+    assert_eq!(u64::from(lsn), 0x16_B374_D848);
+    assert_eq!(lsn.to_string(), "16/B374D848");
+    let row = client.query_one("SELECT $1::PG_LSN::TEXT", &[&lsn]).unwrap();
+    assert_eq!(row.get::<_, &str>(0), "16/B374D848");
+    eprintln!("ok");
+
     eprint!("syntax error ... ");
     let e = client.query_one("foobar", &[]).err().unwrap();
     let e = format!("{e:?}");
@@ -62,6 +154,9 @@ fn main() {
     let row = client.query_one("SELECT 0::INT4", &[]).unwrap();
     let e = row.try_get::<_, i64>(0).unwrap_err().to_string();
     assert!(e.contains("cannot convert between the Rust type `i64` and the Postgres type `int4`"), "{e}");
+    let e = client.query_one("SELECT $1::INT4", &[&"x"]).err().unwrap().to_string();
+    assert!(e.contains("cannot convert between the Rust type `&str` and the Postgres type `int4`"), "{e}");
+    client.query_one("SELECT 1", &[]).unwrap();
     eprintln!("ok");
 
     eprint!("table already exists (notice) ... ");
@@ -73,6 +168,27 @@ fn main() {
         .unwrap();
     eprintln!("ok");
 
+    eprint!("SET reported parameter (ParameterStatus) ... ");
+    client.batch_execute("SET application_name = 'verify'").unwrap();
+    client.batch_execute("RESET application_name").unwrap();
+    eprintln!("ok");
+
+    eprint!("notifications ... ");
+    client.batch_execute("LISTEN verify; NOTIFY verify, 'hello'").unwrap();
+    assert_eq!(client.notifications().len(), 1);
+    let notifications: Vec<_> = client.notifications().iter().collect().unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].channel(), "verify");
+    assert_eq!(notifications[0].payload(), "hello");
+    assert!(client.notifications().iter().next().unwrap().is_none());
+    client.batch_execute("NOTIFY verify, 'again'").unwrap();
+    let n = client.notifications().blocking_iter().next().unwrap().unwrap();
+    assert_eq!(n.payload(), "again");
+    let timeout = std::time::Duration::from_millis(50);
+    assert!(client.notifications().timeout_iter(timeout).next().unwrap().is_none());
+    client.batch_execute("UNLISTEN verify").unwrap();
+    eprintln!("ok");
+
     eprint!("batch_execute ... ");
     client.batch_execute("
         CREATE TEMP TABLE test (id INT PRIMARY KEY, value TEXT);
@@ -85,6 +201,20 @@ fn main() {
     assert_eq!(count, 2);
     eprintln!("ok");
 
+    eprint!("simple_query ... ");
+    let messages = client.simple_query("SELECT 1 AS a, NULL AS b; SELECT 'x' AS c").unwrap();
+    assert_eq!(messages.len(), 6);
+    let postgres::SimpleQueryMessage::RowDescription(columns) = &messages[0] else { panic!() };
+    assert_eq!(columns.iter().map(|c| c.name()).collect::<Vec<_>>(), ["a", "b"]);
+    let postgres::SimpleQueryMessage::Row(row) = &messages[1] else { panic!() };
+    assert_eq!(row.get(0), Some("1"));
+    assert_eq!(row.get("b"), None);
+    assert!(matches!(messages[2], postgres::SimpleQueryMessage::CommandComplete(1)));
+    let postgres::SimpleQueryMessage::Row(row) = &messages[4] else { panic!() };
+    assert_eq!(row.get("c"), Some("x"));
+    assert!(matches!(messages[5], postgres::SimpleQueryMessage::CommandComplete(1)));
+    eprintln!("ok");
+
     eprint!("query ... ");
     let rows = client.query("SELECT value FROM test ORDER BY id", &[]).unwrap();
     assert_eq!(rows.len(), 2);
@@ -135,6 +265,23 @@ fn main() {
     assert_eq!(two, 2);
     eprintln!("ok");
 
+    eprint!("row.columns ... ");
+    let row = client.query_one("SELECT id, value, 1 AS one FROM test LIMIT 1", &[]).unwrap();
+    let columns = row.columns();
+    assert_eq!(columns.iter().map(|c| c.name()).collect::<Vec<_>>(), ["id", "value", "one"]);
+    assert_eq!(columns[0].type_(), &postgres::types::Type::INT4);
+    assert_eq!(columns[1].type_(), &postgres::types::Type::TEXT);
+    assert!(columns[0].table_oid().is_some());
+    assert_eq!(columns[1].column_id(), Some(2));
+    assert_eq!((columns[2].table_oid(), columns[2].column_id()), (None, None));
+    eprintln!("ok");
+
+    eprint!("row.try_get missing column ... ");
+    let row = client.query_one("SELECT 1 AS one", &[]).unwrap();
+    let e = row.try_get::<_, i32>("two").unwrap_err().to_string();
+    assert!(e.contains("invalid column `two`"), "{e}");
+    eprintln!("ok");
+
     eprint!("row.try_get ... ");
     let row = client.query_one("SELECT 42", &[]).unwrap();
     let answer: i32 = row.try_get(0).unwrap();
@@ -153,6 +300,19 @@ fn main() {
     assert_eq!(count, 3);
     eprintln!("ok");
 
+    eprint!("ANY($1) array parameter ... ");
+    client.batch_execute("CREATE TEMP TABLE any_test AS SELECT generate_series(1, 5) AS id").unwrap();
+    let rows = client
+        .query("SELECT id FROM any_test WHERE id = ANY($1) ORDER BY id", &[&vec![2i32, 4, 6]])
+        .unwrap();
+    assert_eq!(rows.iter().map(|r| r.get(0)).collect::<Vec<i32>>(), [2, 4]);
+    let n = client.execute("DELETE FROM any_test WHERE id = ANY($1)", &[&Vec::<i32>::new()]).unwrap();
+    assert_eq!(n, 0);
+    let ids: &[i32] = &[1, 2, 3];
+    let n = client.execute("DELETE FROM any_test WHERE id = ANY($1)", &[&ids]).unwrap();
+    assert_eq!(n, 3);
+    eprintln!("ok");
+
     eprint!("transaction commit ... ");
     {
         let mut tx = client.transaction().unwrap();
@@ -184,6 +344,60 @@ fn main() {
     assert_eq!(count, 4);
     eprintln!("ok");
 
+    eprint!("commit after a failed statement ... ");
+    {
+        let mut tx = client.transaction().unwrap();
+        tx.execute("INSERT INTO test VALUES ($1, $2)", &[&6i32, &"six"]).unwrap();
+        tx.execute("INSERT INTO test VALUES ($1, $2)", &[&6i32, &"six"]).unwrap_err();
+        tx.commit().unwrap();  // silently rolled back
+    }
+    let count: i64 = client
+        .query_one("SELECT COUNT(*) FROM test WHERE id = 6", &[])
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 0);
+    eprintln!("ok");
+
+    eprint!("savepoints ... ");
+    {
+        let mut tx = client.transaction().unwrap();
+        tx.execute("INSERT INTO test VALUES ($1, $2)", &[&7i32, &"seven"]).unwrap();
+        {
+            let mut sp = tx.savepoint("before_eight").unwrap();
+            sp.execute("INSERT INTO test VALUES ($1, $2)", &[&8i32, &"eight"]).unwrap();
+            sp.rollback().unwrap();
+        }
+        {
+            let mut nested = tx.transaction().unwrap();
+            nested.execute("INSERT INTO test VALUES ($1, $2)", &[&9i32, &"nine"]).unwrap();
+            let mut inner = nested.transaction().unwrap();
+            inner.execute("INSERT INTO test VALUES ($1, $2)", &[&9i32, &"nine"]).unwrap_err();
+            drop(inner);
+            nested.commit().unwrap();
+        }
+        tx.commit().unwrap();
+    }
+    let ids: Vec<i32> = client
+        .query("SELECT id FROM test WHERE id >= 7 ORDER BY id", &[])
+        .unwrap()
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    assert_eq!(ids, [7, 9]);
+    eprintln!("ok");
+
+    eprint!("cancel_token ... ");
+    let token = client.cancel_token();
+    let canceller = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        token.clone().cancel_query(postgres::NoTls).unwrap();
+    });
+    let e = client.batch_execute("SELECT pg_sleep(10)").unwrap_err();
+    assert!(e.to_string().contains("canceling statement due to user request"), "{e}");
+    canceller.join().unwrap();
+    client.query_one("SELECT 1", &[]).unwrap();
+    eprintln!("ok");
+
     json::run(&mut client);
     chrono::run(&mut client);
 }